@@ -11,13 +11,16 @@ use crossterm::{
     },
 };
 use rand::Rng;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::io::{stdout, IsTerminal, Write};
 use std::time::{Duration, Instant};
 
 // Character sets
 const KATAKANA: &str = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヲン";
 const ASCII_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!@#$%^&*()_+-=[]{}|;:',.<>?/";
+const BINARY_CHARS: &str = "01";
+const HEX_CHARS: &str = "0123456789ABCDEF";
+const EMOJI_CHARS: &str = "😀😃😄😁😆😅😂🤣😊😇🙂🙃😉😌😍🥰😘😗😙😚😋😛😝😜🤪🤨🧐🤓😎🥳";
 
 // Speed tiers (cells per second) - 1x/2x/3x for depth perception
 const SPEED_TIERS: [f64; 3] = [8.0, 16.0, 24.0];
@@ -30,13 +33,12 @@ const MUTATION_RATE: f64 = 0.10; // 10% per frame
 
 // Timing
 const TARGET_FPS: u64 = 30;
-const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / TARGET_FPS);
 
 // Terminal requirements
 const MIN_WIDTH: u16 = 20;
 const MIN_HEIGHT: u16 = 10;
 
-// Color definitions for gradient
+// Default color gradient
 const COLOR_HEAD: Color = Color::Rgb { r: 255, g: 255, b: 255 }; // Bright white
 const COLOR_BRIGHT: Color = Color::Rgb { r: 0, g: 255, b: 0 };   // Bright green
 const COLOR_MEDIUM: Color = Color::Rgb { r: 0, g: 215, b: 0 };   // Medium green
@@ -47,11 +49,227 @@ fn get_char_set() -> Vec<char> {
     KATAKANA.chars().chain(ASCII_CHARS.chars()).collect()
 }
 
+/// Direction the rain flows in. Down/Up travel along the `y` axis with
+/// `x` held fixed per column; Left/Right travel along `x` with `y` held
+/// fixed, turning each "column" into a row.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Direction {
+    Down,
+    Up,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn parse(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "up" => Direction::Up,
+            "left" => Direction::Left,
+            "right" => Direction::Right,
+            _ => Direction::Down,
+        }
+    }
+}
+
+/// Resolve a `--chars` argument to a character set: a recognized group
+/// name, or a user-supplied literal string used verbatim.
+fn char_set_for_group(group: &str) -> Vec<char> {
+    match group {
+        "katakana" => KATAKANA.chars().collect(),
+        "ascii" => ASCII_CHARS.chars().collect(),
+        "binary" => BINARY_CHARS.chars().collect(),
+        "hex" => HEX_CHARS.chars().collect(),
+        "emoji" => EMOJI_CHARS.chars().collect(),
+        custom => custom.chars().collect(),
+    }
+}
+
+/// Parse a `#RRGGBB` or `RRGGBB` hex string into an RGB `Color`.
+fn parse_color(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some(Color::Rgb { r, g, b })
+}
+
+/// A bounded region of the real terminal screen, used for inline mode.
+#[derive(Clone, Copy, Debug)]
+struct Rect {
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+}
+
+impl Rect {
+    /// Parse a `x,y,width,height` string as produced by `--inline`.
+    fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split(',').map(|p| p.trim().parse::<u16>());
+        Some(Rect {
+            x: parts.next()?.ok()?,
+            y: parts.next()?.ok()?,
+            width: parts.next()?.ok()?,
+            height: parts.next()?.ok()?,
+        })
+    }
+}
+
+/// User-configurable appearance and behavior, built from CLI arguments.
+struct Config {
+    char_set: Vec<char>,
+    color_head: Color,
+    color_bright: Color,
+    color_medium: Color,
+    color_dim: Color,
+    density: f64,
+    fps: u64,
+    direction: Direction,
+    inline: Option<Rect>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            char_set: get_char_set(),
+            color_head: COLOR_HEAD,
+            color_bright: COLOR_BRIGHT,
+            color_medium: COLOR_MEDIUM,
+            color_dim: COLOR_DIM,
+            density: COLUMN_DENSITY,
+            fps: TARGET_FPS,
+            direction: Direction::Down,
+            inline: None,
+        }
+    }
+}
+
+/// Parse CLI arguments (program name already stripped) into a `Config`,
+/// falling back to defaults for anything unspecified or malformed.
+///
+/// Recognized flags: `--chars`, `--head-color`, `--bright-color`,
+/// `--medium-color`, `--dim-color`, `--density`, `--fps`, `--direction`,
+/// `--inline x,y,width,height`.
+fn parse_args<I: Iterator<Item = String>>(args: I) -> Config {
+    let mut config = Config::default();
+    let mut args = args.into_iter();
+
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--chars" => {
+                if let Some(v) = args.next() {
+                    let set = char_set_for_group(&v);
+                    // An empty resolved set (e.g. `--chars ""`) would make
+                    // Column::new's `gen_range(0..char_set.len())` panic.
+                    if !set.is_empty() {
+                        config.char_set = set;
+                    }
+                }
+            }
+            "--head-color" => {
+                if let Some(v) = args.next().and_then(|v| parse_color(&v)) {
+                    config.color_head = v;
+                }
+            }
+            "--bright-color" => {
+                if let Some(v) = args.next().and_then(|v| parse_color(&v)) {
+                    config.color_bright = v;
+                }
+            }
+            "--medium-color" => {
+                if let Some(v) = args.next().and_then(|v| parse_color(&v)) {
+                    config.color_medium = v;
+                }
+            }
+            "--dim-color" => {
+                if let Some(v) = args.next().and_then(|v| parse_color(&v)) {
+                    config.color_dim = v;
+                }
+            }
+            "--density" => {
+                // Reject non-finite parses (e.g. "nan") the same way the
+                // empty-`--chars` case above rejects its degenerate input,
+                // rather than let `clamp` pass NaN through unchanged and
+                // silently trim the animation to zero columns.
+                if let Some(v) = args
+                    .next()
+                    .and_then(|v: String| v.parse::<f64>().ok())
+                    .filter(|v| v.is_finite())
+                {
+                    // Same clamp the `[`/`]` keybindings apply at runtime:
+                    // anything above 1.0 would push target_count past the
+                    // number of physical slots, which `spawn_new_column`
+                    // can never fill.
+                    config.density = v.clamp(0.0, 1.0);
+                }
+            }
+            "--fps" => {
+                if let Some(v) = args.next().and_then(|v| v.parse().ok()) {
+                    config.fps = v;
+                }
+            }
+            "--direction" => {
+                if let Some(v) = args.next() {
+                    config.direction = Direction::parse(&v);
+                }
+            }
+            "--inline" => {
+                if let Some(v) = args.next().and_then(|v| Rect::parse(&v)) {
+                    config.inline = Some(v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    config
+}
+
+/// Display width of a character in terminal columns: 0 for combining/zero-width
+/// marks, 2 for East-Asian fullwidth glyphs (including katakana), 1 otherwise.
+///
+/// This is a small, hand-rolled approximation of `wcwidth` covering the
+/// ranges this animation actually emits rather than the full Unicode table.
+fn char_width(ch: char) -> u8 {
+    let c = ch as u32;
+    let zero_width = matches!(c, 0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F);
+    if zero_width {
+        return 0;
+    }
+
+    let fullwidth = matches!(
+        c,
+        0x1100..=0x115F   // Hangul Jamo
+        | 0x2E80..=0xA4CF // CJK radicals, Kana, CJK Unified Ideographs, etc.
+        | 0xAC00..=0xD7A3 // Hangul syllables
+        | 0xF900..=0xFAFF // CJK compatibility ideographs
+        | 0xFF00..=0xFF60 // Fullwidth forms
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F // Misc symbols and pictographs, emoticons
+        | 0x1F900..=0x1F9FF // Supplemental symbols and pictographs
+    );
+    if fullwidth {
+        2
+    } else {
+        1
+    }
+}
+
 /// Represents a single falling rain column.
+///
+/// `fixed_pos` is the coordinate held constant as the column travels:
+/// `x` for vertical directions (Down/Up), `y` for horizontal ones
+/// (Left/Right), where the column is really a row. `travel_bound` is the
+/// length of the axis it travels along, and `head` its position on that
+/// axis — it only ever increases, regardless of direction, and
+/// `map_to_screen` turns it into screen coordinates.
 struct Column {
-    x: u16,
-    screen_height: u16,
-    y_head: f64,
+    fixed_pos: u16,
+    travel_bound: u16,
+    head: f64,
     speed: f64,
     trail_length: usize,
     characters: Vec<char>,
@@ -59,7 +277,7 @@ struct Column {
 }
 
 impl Column {
-    fn new(x: u16, screen_height: u16, char_set: &[char]) -> Self {
+    fn new(fixed_pos: u16, travel_bound: u16, char_set: &[char]) -> Self {
         let mut rng = rand::thread_rng();
         let speed_tier = rng.gen_range(0..3);
         let trail_length = rng.gen_range(TRAIL_LENGTH_MIN..=TRAIL_LENGTH_MAX);
@@ -68,9 +286,9 @@ impl Column {
             .collect();
 
         Column {
-            x,
-            screen_height,
-            y_head: 0.0,
+            fixed_pos,
+            travel_bound,
+            head: 0.0,
             speed: SPEED_TIERS[speed_tier],
             trail_length,
             characters,
@@ -78,12 +296,12 @@ impl Column {
         }
     }
 
-    /// Move column down by delta_time * speed.
+    /// Advance the head by delta_time * speed.
     fn update(&mut self, delta_time: f64) {
-        self.y_head += self.speed * delta_time;
+        self.head += self.speed * delta_time;
 
-        // Check if fully off screen (head + trail length past bottom)
-        if self.y_head - self.trail_length as f64 > self.screen_height as f64 {
+        // Check if fully off screen (head + trail length past the end)
+        if self.head - self.trail_length as f64 > self.travel_bound as f64 {
             self.active = false;
         }
     }
@@ -98,35 +316,105 @@ impl Column {
         }
     }
 
+    /// Redraw every character in the trail from scratch.
+    fn reseed(&mut self, char_set: &[char]) {
+        let mut rng = rand::thread_rng();
+        for ch in &mut self.characters {
+            *ch = char_set[rng.gen_range(0..char_set.len())];
+        }
+    }
+
     /// Return list of (x, y, char, color) for visible cells.
-    fn get_visible_cells(&self) -> Vec<(u16, u16, char, Color)> {
+    ///
+    /// For `Direction::Left`, trail index `i` maps to strictly increasing
+    /// `x`, so a wide glyph's reserved spacer at `x+1` is exactly the cell
+    /// the next trail character would render into. Skip that slot here
+    /// rather than letting the renderer's spacer guard silently drop it.
+    ///
+    /// For `Direction::Right`, `i` maps to strictly *decreasing* `x`, so
+    /// the spacer instead lands on the cell the *previous* (already
+    /// pushed) trail character occupies. There's nothing to skip forward
+    /// at that point, so drop the earlier cell instead.
+    fn get_visible_cells(&self, config: &Config) -> Vec<(u16, u16, char, Color)> {
         let mut cells = Vec::new();
-        let head_y = self.y_head as i32;
+        let head_pos = self.head as i32;
+        let self_collides_left = config.direction == Direction::Left;
+        let self_collides_right = config.direction == Direction::Right;
+        let mut skip_next = false;
 
         for i in 0..self.trail_length {
-            let y = head_y - i as i32;
-            if y >= 0 && y < self.screen_height as i32 {
+            if skip_next {
+                skip_next = false;
+                continue;
+            }
+
+            let travel = head_pos - i as i32;
+            if travel >= 0 && travel < self.travel_bound as i32 {
                 let char_idx = i % self.characters.len();
-                let color = self.get_color_for_position(i);
-                cells.push((self.x, y as u16, self.characters[char_idx], color));
+                let ch = self.characters[char_idx];
+                let color = self.get_color_for_position(i, config);
+                let (x, y) = self.map_to_screen(travel as u16, config.direction);
+                cells.push((x, y, ch, color));
+
+                if char_width(ch) == 2 {
+                    if self_collides_left {
+                        skip_next = true;
+                    } else if self_collides_right && cells.len() >= 2 {
+                        cells.remove(cells.len() - 2);
+                    }
+                }
             }
         }
 
         cells
     }
 
+    /// Map a (fixed_pos, travel) pair onto screen (x, y) per direction.
+    fn map_to_screen(&self, travel: u16, direction: Direction) -> (u16, u16) {
+        match direction {
+            Direction::Down => (self.fixed_pos, travel),
+            Direction::Up => (self.fixed_pos, self.travel_bound - 1 - travel),
+            Direction::Right => (travel, self.fixed_pos),
+            Direction::Left => (self.travel_bound - 1 - travel, self.fixed_pos),
+        }
+    }
+
     /// Determine color based on position in trail.
-    fn get_color_for_position(&self, pos: usize) -> Color {
+    fn get_color_for_position(&self, pos: usize, config: &Config) -> Color {
         if pos == 0 {
-            return COLOR_HEAD; // White head
+            return config.color_head;
         }
         let ratio = pos as f64 / self.trail_length as f64;
         if ratio < 0.33 {
-            COLOR_BRIGHT // Bright green
+            config.color_bright
         } else if ratio < 0.66 {
-            COLOR_MEDIUM // Medium green
+            config.color_medium
         } else {
-            COLOR_DIM // Dim green
+            config.color_dim
+        }
+    }
+}
+
+/// A single screen cell in the front/back buffers.
+///
+/// `spacer` marks the trailing half of a width-2 glyph placed in the
+/// previous column: the diff renderer must not print into it (the glyph
+/// already occupies it on the real terminal grid), but it still
+/// participates in front/back comparison so it gets blanked once the
+/// glyph that reserved it moves on.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    ch: char,
+    fg: Color,
+    spacer: bool,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            ch: ' ',
+            fg: Color::Reset,
+            spacer: false,
         }
     }
 }
@@ -135,31 +423,76 @@ impl Column {
 struct MatrixRain {
     height: u16,
     width: u16,
+    origin: (u16, u16),
+    inline: bool,
+    /// Fixed `(width, height)` of the inline viewport, unused outside
+    /// inline mode. `resize` clips against this instead of the raw
+    /// terminal size so the animation never stretches past its box.
+    inline_target: (u16, u16),
     columns: Vec<Column>,
     column_slots: HashSet<u16>,
     running: bool,
-    prev_frame: HashMap<(u16, u16), (char, Color)>,
-    char_set: Vec<char>,
+    paused: bool,
+    speed_multiplier: f64,
+    front: Vec<Cell>,
+    back: Vec<Cell>,
+    config: Config,
 }
 
 impl MatrixRain {
-    fn new(width: u16, height: u16) -> Self {
+    /// Build a full-screen animation covering `width` x `height`.
+    fn new(width: u16, height: u16, config: Config) -> Self {
+        let area = width as usize * height as usize;
         MatrixRain {
             height,
             width,
+            origin: (0, 0),
+            inline: false,
+            inline_target: (width, height),
             columns: Vec::new(),
             column_slots: HashSet::new(),
             running: true,
-            prev_frame: HashMap::new(),
-            char_set: get_char_set(),
+            paused: false,
+            speed_multiplier: 1.0,
+            front: vec![Cell::default(); area],
+            back: vec![Cell::default(); area],
+            config,
+        }
+    }
+
+    /// Build an animation confined to `area`, leaving everything outside
+    /// it on the real screen untouched.
+    fn new_inline(area: Rect, config: Config) -> Self {
+        let mut rain = Self::new(area.width, area.height, config);
+        rain.origin = (area.x, area.y);
+        rain.inline = true;
+        rain
+    }
+
+    /// Length of the axis columns are distributed along: `width` for
+    /// vertical rain, `height` once it flows Left/Right.
+    fn cross_axis_len(&self) -> u16 {
+        match self.config.direction {
+            Direction::Down | Direction::Up => self.width,
+            Direction::Left | Direction::Right => self.height,
+        }
+    }
+
+    /// Length of the axis columns travel along.
+    fn travel_bound(&self) -> u16 {
+        match self.config.direction {
+            Direction::Down | Direction::Up => self.height,
+            Direction::Left | Direction::Right => self.width,
         }
     }
 
     /// Spawn columns to achieve target density immediately.
     fn spawn_initial_columns(&mut self) {
         let mut rng = rand::thread_rng();
-        let target_count = (self.width as f64 * COLUMN_DENSITY) as usize;
-        let mut available_slots: Vec<u16> = (0..self.width).collect();
+        let cross_axis_len = self.cross_axis_len();
+        let travel_bound = self.travel_bound();
+        let target_count = (cross_axis_len as f64 * self.config.density) as usize;
+        let mut available_slots: Vec<u16> = (0..cross_axis_len).collect();
 
         // Shuffle available slots
         for i in (1..available_slots.len()).rev() {
@@ -167,124 +500,339 @@ impl MatrixRain {
             available_slots.swap(i, j);
         }
 
-        for &x in available_slots.iter().take(target_count) {
-            let mut col = Column::new(x, self.height, &self.char_set);
+        for &fixed_pos in available_slots.iter().take(target_count) {
+            let mut col = Column::new(fixed_pos, travel_bound, &self.config.char_set);
             // Randomize starting position for varied entry
-            col.y_head = rng.gen_range(-(col.trail_length as f64)..self.height as f64);
+            col.head = rng.gen_range(-(col.trail_length as f64)..travel_bound as f64);
             self.columns.push(col);
-            self.column_slots.insert(x);
+            self.column_slots.insert(fixed_pos);
         }
     }
 
+    /// Whether any row currently shows `x` as the reserved trailing half
+    /// of a wide glyph drawn from the column to its left. Only meaningful
+    /// for vertical rain, where a column's slot is a physical screen
+    /// column; horizontal rain has no such constraint.
+    fn is_wide_spacer_column(&self, x: u16) -> bool {
+        let width = self.width as usize;
+        (0..self.height).any(|y| self.front[y as usize * width + x as usize].spacer)
+    }
+
     /// Spawn a new column at random available position.
-    fn spawn_new_column(&mut self) {
+    ///
+    /// Free positions shadowed by a wide-glyph spacer in last frame's
+    /// `front` are avoided, but used anyway when no other free position
+    /// is left. Returns `false` when there's nowhere to put one — every
+    /// cross-axis position already holds a column. Callers must treat that
+    /// as "can't reach density this call" rather than retrying in a loop:
+    /// nothing frees a position in between, so looping here would never
+    /// terminate.
+    fn spawn_new_column(&mut self) -> bool {
         let mut rng = rand::thread_rng();
-        let available: Vec<u16> = (0..self.width)
-            .filter(|x| !self.column_slots.contains(x))
+        let cross_axis_len = self.cross_axis_len();
+        let travel_bound = self.travel_bound();
+        let vertical = matches!(self.config.direction, Direction::Down | Direction::Up);
+        let mut available: Vec<u16> = (0..cross_axis_len)
+            .filter(|&f| {
+                !self.column_slots.contains(&f) && (!vertical || !self.is_wide_spacer_column(f))
+            })
             .collect();
 
-        if !available.is_empty() {
-            let x = available[rng.gen_range(0..available.len())];
-            let mut col = Column::new(x, self.height, &self.char_set);
-            col.y_head = 0.0; // Start from top
+        // Every free slot is wide-glyph shadowed; fall back to ignoring
+        // that constraint rather than leaving the slot permanently unfilled.
+        if available.is_empty() {
+            available = (0..cross_axis_len)
+                .filter(|f| !self.column_slots.contains(f))
+                .collect();
+        }
+
+        if let Some(&fixed_pos) = available.get(rng.gen_range(0..available.len().max(1))) {
+            let col = Column::new(fixed_pos, travel_bound, &self.config.char_set);
             self.columns.push(col);
-            self.column_slots.insert(x);
+            self.column_slots.insert(fixed_pos);
+            true
+        } else {
+            false
         }
     }
 
     /// Update all columns and manage spawning.
     fn update(&mut self, delta_time: f64) {
+        if self.paused {
+            return;
+        }
+
         // Update existing columns
+        let scaled_dt = delta_time * self.speed_multiplier;
         for col in &mut self.columns {
-            col.update(delta_time);
-            col.mutate(&self.char_set);
+            col.update(scaled_dt);
+            col.mutate(&self.config.char_set);
         }
 
         // Remove inactive columns and free their slots
-        let inactive_x: Vec<u16> = self
+        let inactive: Vec<u16> = self
             .columns
             .iter()
             .filter(|c| !c.active)
-            .map(|c| c.x)
+            .map(|c| c.fixed_pos)
             .collect();
 
-        for x in inactive_x {
-            self.column_slots.remove(&x);
+        for fixed_pos in inactive {
+            self.column_slots.remove(&fixed_pos);
         }
         self.columns.retain(|c| c.active);
 
-        // Spawn replacements to maintain density
-        let target_count = (self.width as f64 * COLUMN_DENSITY) as usize;
+        // Maintain density (also picks up runtime `[`/`]` density changes
+        // from handle_input): spawn replacements if below target, or trim
+        // the newest columns immediately if above it, rather than waiting
+        // for excess columns to scroll off on their own.
+        let target_count = (self.cross_axis_len() as f64 * self.config.density) as usize;
         while self.column_slots.len() < target_count {
-            self.spawn_new_column();
+            if !self.spawn_new_column() {
+                break;
+            }
+        }
+        while self.columns.len() > target_count {
+            if let Some(col) = self.columns.pop() {
+                self.column_slots.remove(&col.fixed_pos);
+            }
+        }
+    }
+
+    /// Reseed every column's characters, as triggered by the `r` key.
+    fn reseed_all(&mut self) {
+        for col in &mut self.columns {
+            col.reseed(&self.config.char_set);
         }
     }
 
     /// Render frame with differential updates.
+    ///
+    /// `back` is rebuilt from scratch each frame, then diffed against
+    /// `front` in row-major order so cost is proportional to screen area
+    /// rather than column count. Adjacent changed cells on a row share a
+    /// single `MoveTo`, followed by one `SetForegroundColor` + `Print`
+    /// per cell, with wide-glyph spacers skipped (the cursor advances on
+    /// its own between them).
     fn render(&mut self, stdout: &mut std::io::Stdout) -> std::io::Result<()> {
-        // Build current frame state
-        let mut current_frame: HashMap<(u16, u16), (char, Color)> = HashMap::new();
+        let width = self.width as usize;
+
+        for cell in &mut self.back {
+            *cell = Cell::default();
+        }
+
+        // Wide glyphs reserve their trailing spacer in a second pass below,
+        // applied after every column has drawn, so the reservation always
+        // wins regardless of which column happened to run first (columns
+        // are iterated in `Vec` order, not screen order). The glyph's real
+        // cursor already advanced two physical columns when printed, so
+        // whatever a neighbor wrote into that cell this frame must be
+        // discarded rather than printed — letting it win would need a
+        // second `MoveTo` mid-run to resync the cursor with the index the
+        // print loop thinks it's at.
+        let mut pending_spacers: Vec<(usize, Color)> = Vec::new();
 
         for col in &self.columns {
-            for (x, y, ch, color) in col.get_visible_cells() {
+            for (x, y, ch, color) in col.get_visible_cells(&self.config) {
                 // Avoid bottom-right corner (terminal quirk)
                 if x < self.width && y < self.height {
                     if x == self.width - 1 && y == self.height - 1 {
                         continue;
                     }
-                    current_frame.insert((x, y), (ch, color));
+
+                    let idx = y as usize * width + x as usize;
+                    match char_width(ch) {
+                        0 => continue,
+                        2 => {
+                            self.back[idx] = Cell {
+                                ch,
+                                fg: color,
+                                spacer: false,
+                            };
+                            if x + 1 < self.width {
+                                pending_spacers.push((idx + 1, color));
+                            }
+                        }
+                        _ => {
+                            self.back[idx] = Cell {
+                                ch,
+                                fg: color,
+                                spacer: false,
+                            };
+                        }
+                    }
                 }
             }
         }
 
-        // Clear cells that were drawn last frame but not this frame
-        for pos in self.prev_frame.keys() {
-            if !current_frame.contains_key(pos) {
-                let (x, y) = *pos;
-                execute!(stdout, MoveTo(x, y), Print(" "))?;
+        // Claim each reserved spacer, overwriting any real character a
+        // neighbor drew there this frame: the wide glyph's print already
+        // consumed that physical column, so the neighbor's content can't
+        // be shown there without desyncing the cursor. Spacers are applied
+        // left to right, and one whose own glyph was just overwritten by a
+        // spacer from further left is dropped: that glyph is never printed,
+        // so nothing consumes the column after it.
+        pending_spacers.sort_unstable_by_key(|&(idx, _)| idx);
+        for (idx, color) in pending_spacers {
+            if self.back[idx - 1].spacer {
+                continue;
             }
+            self.back[idx] = Cell {
+                ch: ' ',
+                fg: color,
+                spacer: true,
+            };
         }
 
-        // Draw new/changed cells
-        for (pos, (ch, color)) in &current_frame {
-            if !self.prev_frame.contains_key(pos) || self.prev_frame.get(pos) != Some(&(*ch, *color))
-            {
-                let (x, y) = *pos;
-                execute!(
-                    stdout,
-                    MoveTo(x, y),
-                    SetForegroundColor(*color),
-                    Print(ch)
-                )?;
+        for y in 0..self.height {
+            let row = y as usize * width;
+            let mut x: u16 = 0;
+            while (x as usize) < width {
+                let idx = row + x as usize;
+                if self.back[idx] == self.front[idx] {
+                    x += 1;
+                    continue;
+                }
+
+                execute!(stdout, MoveTo(self.origin.0 + x, self.origin.1 + y))?;
+                while (x as usize) < width && self.back[row + x as usize] != self.front[row + x as usize]
+                {
+                    let cell = self.back[row + x as usize];
+                    // The glyph to our left already advanced the real
+                    // cursor past this column; printing here would
+                    // duplicate it and desync the grid.
+                    if !cell.spacer {
+                        execute!(stdout, SetForegroundColor(cell.fg), Print(cell.ch))?;
+                    }
+                    x += 1;
+                }
             }
         }
 
-        self.prev_frame = current_frame;
+        std::mem::swap(&mut self.front, &mut self.back);
         stdout.flush()?;
         Ok(())
     }
 
-    /// Handle keyboard input.
+    /// Handle keyboard and terminal events.
     fn handle_input(&mut self) -> std::io::Result<()> {
         // Poll for events with zero timeout (non-blocking)
         if poll(Duration::ZERO)? {
-            if let Event::Key(key_event) = read()? {
-                match key_event.code {
+            match read()? {
+                Event::Key(key_event) => match key_event.code {
                     KeyCode::Char('q') => self.running = false,
                     KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
                         self.running = false
                     }
                     KeyCode::Esc => self.running = false,
+                    KeyCode::Char(' ') => self.paused = !self.paused,
+                    KeyCode::Char('+') | KeyCode::Char('=') => {
+                        self.speed_multiplier = (self.speed_multiplier * 1.1).min(8.0)
+                    }
+                    KeyCode::Char('-') => {
+                        self.speed_multiplier = (self.speed_multiplier / 1.1).max(0.1)
+                    }
+                    KeyCode::Char('[') => self.config.density = (self.config.density - 0.05).max(0.0),
+                    KeyCode::Char(']') => self.config.density = (self.config.density + 0.05).min(1.0),
+                    KeyCode::Char('r') => self.reseed_all(),
                     _ => {}
+                },
+                Event::Resize(w, h) => {
+                    self.resize(w, h);
+                    let _ = self.clear_region();
                 }
+                _ => {}
             }
         }
         Ok(())
     }
 
+    /// Resize the grid in place, preserving as much state as possible.
+    ///
+    /// `term_width`/`term_height` are the raw terminal dimensions reported
+    /// by the resize event. In inline mode the animation never grows past
+    /// `inline_target`, the fixed viewport size chosen at startup — it
+    /// only shrinks if the terminal becomes too small to fit it at
+    /// `origin`. In full-screen mode the grid simply tracks the terminal.
+    ///
+    /// Columns that fall outside a shrunken cross-axis are dropped and
+    /// their slots freed; growing it tops up toward the density target.
+    /// The differential renderer has no notion of the old geometry, so
+    /// the buffers are reallocated. This only updates in-memory state;
+    /// callers driving a real terminal must follow up with
+    /// `clear_region()` to blank away stale content (kept separate so
+    /// this method stays pure and safe to exercise from unit tests).
+    fn resize(&mut self, term_width: u16, term_height: u16) {
+        let (new_width, new_height) = if self.inline {
+            (
+                self.inline_target.0.min(term_width.saturating_sub(self.origin.0)),
+                self.inline_target.1.min(term_height.saturating_sub(self.origin.1)),
+            )
+        } else {
+            (term_width, term_height)
+        };
+
+        let new_cross_axis_len = match self.config.direction {
+            Direction::Down | Direction::Up => new_width,
+            Direction::Left | Direction::Right => new_height,
+        };
+
+        if new_cross_axis_len < self.cross_axis_len() {
+            let dropped: Vec<u16> = self
+                .columns
+                .iter()
+                .filter(|c| c.fixed_pos >= new_cross_axis_len)
+                .map(|c| c.fixed_pos)
+                .collect();
+            for fixed_pos in dropped {
+                self.column_slots.remove(&fixed_pos);
+            }
+            self.columns.retain(|c| c.fixed_pos < new_cross_axis_len);
+        }
+
+        self.width = new_width;
+        self.height = new_height;
+
+        // Reallocate before spawning: `spawn_new_column` inspects `front`
+        // at the new geometry via `is_wide_spacer_column`, so it must
+        // already be sized to match `self.width`/`self.height`.
+        let area = self.width as usize * self.height as usize;
+        self.front = vec![Cell::default(); area];
+        self.back = vec![Cell::default(); area];
+
+        let new_travel_bound = self.travel_bound();
+        for col in &mut self.columns {
+            col.travel_bound = new_travel_bound;
+        }
+
+        let target_count = (new_cross_axis_len as f64 * self.config.density) as usize;
+        while self.column_slots.len() < target_count {
+            if !self.spawn_new_column() {
+                break;
+            }
+        }
+    }
+
+    /// Blank the animation's footprint on the real screen: the whole
+    /// terminal in full-screen mode, or just `origin`..`origin+size` when
+    /// confined to an inline viewport.
+    fn clear_region(&self) -> std::io::Result<()> {
+        let mut out = stdout();
+        if self.inline {
+            let blank_row = " ".repeat(self.width as usize);
+            for row in 0..self.height {
+                execute!(out, MoveTo(self.origin.0, self.origin.1 + row), Print(&blank_row))?;
+            }
+            Ok(())
+        } else {
+            execute!(out, Clear(ClearType::All))
+        }
+    }
+
     /// Main loop with frame pacing.
     fn run(&mut self) -> std::io::Result<()> {
         let mut stdout = stdout();
+        let frame_time = Duration::from_nanos(1_000_000_000 / self.config.fps.max(1));
 
         // Initialize
         self.spawn_initial_columns();
@@ -306,8 +854,8 @@ impl MatrixRain {
 
             // Frame pacing
             let elapsed = current_time.elapsed();
-            if elapsed < FRAME_TIME {
-                std::thread::sleep(FRAME_TIME - elapsed);
+            if elapsed < frame_time {
+                std::thread::sleep(frame_time - elapsed);
             }
 
             last_time = current_time;
@@ -324,8 +872,11 @@ fn main() {
         std::process::exit(1);
     }
 
+    let config = parse_args(std::env::args().skip(1));
+    let inline_area = config.inline;
+
     // Get terminal size and validate
-    let (width, height) = match terminal::size() {
+    let (term_width, term_height) = match terminal::size() {
         Ok(size) => size,
         Err(e) => {
             eprintln!(
@@ -338,26 +889,49 @@ fn main() {
         }
     };
 
-    if width < MIN_WIDTH || height < MIN_HEIGHT {
-        eprintln!(
-            "Error: Terminal too small: {}x{}. Minimum size: {}x{}.",
-            width, height, MIN_WIDTH, MIN_HEIGHT
-        );
-        std::process::exit(1);
-    }
+    let mut app = if let Some(area) = inline_area {
+        if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+            eprintln!(
+                "Error: Inline area too small: {}x{}. Minimum size: {}x{}.",
+                area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+            );
+            std::process::exit(1);
+        }
+        let fits_width = area.x.checked_add(area.width).is_some_and(|right| right <= term_width);
+        let fits_height = area.y.checked_add(area.height).is_some_and(|bottom| bottom <= term_height);
+        if !fits_width || !fits_height {
+            eprintln!(
+                "Error: Inline area {}x{} at ({}, {}) does not fit in terminal {}x{}.",
+                area.width, area.height, area.x, area.y, term_width, term_height
+            );
+            std::process::exit(1);
+        }
+        MatrixRain::new_inline(area, config)
+    } else {
+        if term_width < MIN_WIDTH || term_height < MIN_HEIGHT {
+            eprintln!(
+                "Error: Terminal too small: {}x{}. Minimum size: {}x{}.",
+                term_width, term_height, MIN_WIDTH, MIN_HEIGHT
+            );
+            std::process::exit(1);
+        }
+        MatrixRain::new(term_width, term_height, config)
+    };
 
     // Setup terminal
-    if let Err(e) = setup_terminal() {
+    if let Err(e) = setup_terminal(inline_area.is_some()) {
         eprintln!("Error: Failed to setup terminal: {}", e);
         std::process::exit(1);
     }
 
     // Run the animation
-    let mut app = MatrixRain::new(width, height);
     let result = app.run();
 
     // Cleanup terminal (always try to restore state)
-    let _ = cleanup_terminal();
+    if inline_area.is_some() {
+        let _ = app.clear_region();
+    }
+    let _ = cleanup_terminal(inline_area.is_some());
 
     // Handle any errors from the main loop
     if let Err(e) = result {
@@ -366,19 +940,152 @@ fn main() {
     }
 }
 
-fn setup_terminal() -> std::io::Result<()> {
+fn setup_terminal(inline: bool) -> std::io::Result<()> {
     enable_raw_mode()?;
-    execute!(
-        stdout(),
-        EnterAlternateScreen,
-        Hide,
-        Clear(ClearType::All)
-    )?;
-    Ok(())
+    if inline {
+        execute!(stdout(), Hide)
+    } else {
+        execute!(stdout(), EnterAlternateScreen, Hide, Clear(ClearType::All))
+    }
 }
 
-fn cleanup_terminal() -> std::io::Result<()> {
-    execute!(stdout(), Show, LeaveAlternateScreen)?;
+fn cleanup_terminal(inline: bool) -> std::io::Result<()> {
+    execute!(stdout(), Show)?;
+    if !inline {
+        execute!(stdout(), LeaveAlternateScreen)?;
+    }
     disable_raw_mode()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_width_classifies_ascii_katakana_and_combining_marks() {
+        assert_eq!(char_width('A'), 1);
+        assert_eq!(char_width('0'), 1);
+        assert_eq!(char_width('ア'), 2);
+        assert_eq!(char_width('\u{0301}'), 0); // combining acute accent
+    }
+
+    #[test]
+    fn map_to_screen_matches_direction() {
+        let col = Column::new(3, 10, &['x']);
+        assert_eq!(col.map_to_screen(4, Direction::Down), (3, 4));
+        assert_eq!(col.map_to_screen(4, Direction::Up), (3, 5));
+        assert_eq!(col.map_to_screen(4, Direction::Right), (4, 3));
+        assert_eq!(col.map_to_screen(4, Direction::Left), (5, 3));
+    }
+
+    #[test]
+    fn parse_color_accepts_hex_with_or_without_hash() {
+        assert_eq!(parse_color("#ff00aa"), Some(Color::Rgb { r: 0xff, g: 0x00, b: 0xaa }));
+        assert_eq!(parse_color("00ff00"), Some(Color::Rgb { r: 0, g: 0xff, b: 0 }));
+        assert_eq!(parse_color("#zzzzzz"), None);
+        assert_eq!(parse_color("#fff"), None);
+    }
+
+    #[test]
+    fn direction_parse_defaults_to_down() {
+        assert_eq!(Direction::parse("up"), Direction::Up);
+        assert_eq!(Direction::parse("LEFT"), Direction::Left);
+        assert_eq!(Direction::parse("right"), Direction::Right);
+        assert_eq!(Direction::parse("sideways"), Direction::Down);
+    }
+
+    #[test]
+    fn get_visible_cells_skips_collision_slot_going_left() {
+        let config = Config {
+            direction: Direction::Left,
+            ..Config::default()
+        };
+        let mut col = Column::new(0, 5, &['x']);
+        col.trail_length = 5;
+        col.characters = vec!['ア', 'B', 'C', 'D', 'E'];
+        col.head = 4.0;
+
+        let cells = col.get_visible_cells(&config);
+        let chars: Vec<char> = cells.iter().map(|&(_, _, ch, _)| ch).collect();
+        assert_eq!(chars, vec!['ア', 'C', 'D', 'E']);
+    }
+
+    #[test]
+    fn get_visible_cells_drops_collision_slot_going_right() {
+        let config = Config {
+            direction: Direction::Right,
+            ..Config::default()
+        };
+        let mut col = Column::new(0, 5, &['x']);
+        col.trail_length = 5;
+        col.characters = vec!['B', 'ア', 'C', 'D', 'E'];
+        col.head = 4.0;
+
+        let cells = col.get_visible_cells(&config);
+        let chars: Vec<char> = cells.iter().map(|&(_, _, ch, _)| ch).collect();
+        assert_eq!(chars, vec!['ア', 'C', 'D', 'E']);
+    }
+
+    #[test]
+    fn rect_parse_reads_comma_separated_fields() {
+        let rect = Rect::parse("4, 5,80,24").unwrap();
+        assert_eq!((rect.x, rect.y, rect.width, rect.height), (4, 5, 80, 24));
+        assert!(Rect::parse("4,5,80").is_none());
+        assert!(Rect::parse("4,5,80,nope").is_none());
+    }
+
+    #[test]
+    fn resize_clips_inline_viewport_instead_of_stretching() {
+        let area = Rect { x: 10, y: 5, width: 40, height: 20 };
+        let mut rain = MatrixRain::new_inline(area, Config::default());
+
+        // Terminal grows: inline viewport must not stretch past its
+        // original size.
+        rain.resize(200, 100);
+        assert_eq!((rain.width, rain.height), (40, 20));
+
+        // Terminal shrinks below where the viewport would fit: clip to
+        // what's left instead of the old size.
+        rain.resize(30, 12);
+        assert_eq!((rain.width, rain.height), (20, 7));
+    }
+
+    #[test]
+    fn update_trims_excess_columns_when_density_drops() {
+        let config = Config {
+            density: 1.0,
+            ..Config::default()
+        };
+        let mut rain = MatrixRain::new(10, 20, config);
+        rain.spawn_initial_columns();
+        assert_eq!(rain.columns.len(), 10);
+
+        rain.config.density = 0.2;
+        rain.update(0.0);
+        assert_eq!(rain.columns.len(), 2);
+        assert_eq!(rain.column_slots.len(), 2);
+    }
+
+    #[test]
+    fn parse_args_clamps_density_to_unit_range() {
+        let args = |v: &str| vec!["--density".to_string(), v.to_string()].into_iter();
+        assert_eq!(parse_args(args("2.0")).density, 1.0);
+        assert_eq!(parse_args(args("-5")).density, 0.0);
+        assert_eq!(parse_args(args("0.4")).density, 0.4);
+    }
+
+    #[test]
+    fn parse_args_rejects_non_finite_density() {
+        let args = |v: &str| vec!["--density".to_string(), v.to_string()].into_iter();
+        assert_eq!(parse_args(args("nan")).density, COLUMN_DENSITY);
+        assert_eq!(parse_args(args("-nan")).density, COLUMN_DENSITY);
+        assert_eq!(parse_args(args("inf")).density, COLUMN_DENSITY);
+    }
+
+    #[test]
+    fn parse_args_ignores_empty_chars_set() {
+        let args = vec!["--chars".to_string(), String::new()].into_iter();
+        assert_eq!(parse_args(args).char_set, get_char_set());
+    }
+}